@@ -4,6 +4,14 @@ mod lib {
     pub use core::ops::RangeInclusive;
     pub use image;
     pub use rand::prelude::*;
+    pub use rand::rngs::OsRng;
+    // `--seed` reproducibility is a cross-platform, cross-build-future
+    // contract, so `Cargo.toml` must pin these two to an exact version
+    // (`rand_chacha = "=0.3.1"`, `rand_core = "=0.6.4"`) rather than a caret
+    // range a `cargo update` could silently bump. This source tree carries no
+    // tracked manifest to pin in; add the `=` pins when wiring it into one.
+    pub use rand_chacha::ChaCha8Rng;
+    pub use serde::{Deserialize, Serialize};
     pub use std::{fmt::Display, path::Path, fs::File, io::Write};
     pub use thiserror::*;
 }
@@ -32,13 +40,80 @@ fn main() -> Result<()> {
                                 .help(
                                     "Range between [0..255] that maps onto name of a factorio tile. The value is derived from grayscale. Format: $tile-name:$x..$y. Multiple space-separated steps can be provided at once. Entire [0..255] range must be covered"
                                 )
-                                .required(true)
+                                .required_unless_present("preset")
                                 .num_args(1..)
                         ).arg(
                             Arg::new("image")
                                 .short('i')
                                 .long("image")
                                 .help("Path of an input image")
+                                .required_unless_present("preset")
+                        ).arg(
+                            Arg::new("output")
+                                .short('o')
+                                .long("output")
+                                .help("Path of a generated lua script")
+                                .required_unless_present("preset")
+                        ).arg(
+                            Arg::new("blending")
+                                .short('b')
+                                .long("blending")
+                                .help(
+                                    "Does a blending noise pass over grayscale. This can help with blending of feature edges. Value is between 0 - 100(%). 0 disables blending pass, 20 makes smooth transitions and 100 is pure randomness"
+                                )
+                                .default_value("0")
+                        ).arg(
+                            Arg::new("seed")
+                                .short('x')
+                                .long("seed")
+                                .help(
+                                    "64 bit value that initializes PRNG, 0 - pick random seed"
+                                )
+                                .default_value("0")
+                        ).arg(
+                            Arg::new("noise")
+                                .short('n')
+                                .long("noise")
+                                .help(
+                                    "Distribution used by the blending pass: uniform, normal or exponential. The perturbation is signed and zero-centered so edges dither both ways"
+                                )
+                                .default_value("uniform")
+                        ).arg(
+                            Arg::new("preset")
+                                .short('p')
+                                .long("preset")
+                                .help(
+                                    "Path of a TOML preset fully describing the job (image, output, steps, blending, noise, seed, dimensions) across one or more named passes composed into a single lua file. When present it supersedes the other flags"
+                                )
+                        ).arg(
+                            Arg::new("gpu")
+                                .short('g')
+                                .long("gpu")
+                                .action(ArgAction::SetTrue)
+                                .help(
+                                    "Run activation and blending on the GPU via a wgpu compute shader. Requires the `gpu` build feature; the CPU path remains the default and fallback. Only supports uniform noise and single-candidate steps; errors out otherwise"
+                                )
+                        )
+                )
+                .subcommand(
+                    Command::new("texture-synth")
+                        .about(
+                            "Grow an arbitrarily large, seamless grayscale field from a small sample using image quilting, then threshold it into a lua texture file just like `texture`."
+                        )
+                        .arg(
+                            Arg::new("steps")
+                                .short('s')
+                                .long("steps")
+                                .help(
+                                    "Range between [0..255] that maps onto name of a factorio tile. The value is derived from grayscale. Format: $tile-name:$x..$y. Multiple space-separated steps can be provided at once. Entire [0..255] range must be covered"
+                                )
+                                .required(true)
+                                .num_args(1..)
+                        ).arg(
+                            Arg::new("image")
+                                .short('i')
+                                .long("image")
+                                .help("Path of a small grayscale sample image")
                                 .required(true)
                         ).arg(
                             Arg::new("output")
@@ -46,6 +121,18 @@ fn main() -> Result<()> {
                                 .long("output")
                                 .help("Path of a generated lua script")
                                 .required(true)
+                        ).arg(
+                            Arg::new("width")
+                                .short('w')
+                                .long("width")
+                                .help("Width of the synthesized grayscale field")
+                                .required(true)
+                        ).arg(
+                            Arg::new("height")
+                                .short('y')
+                                .long("height")
+                                .help("Height of the synthesized grayscale field")
+                                .required(true)
                         ).arg(
                             Arg::new("blending")
                                 .short('b')
@@ -62,6 +149,14 @@ fn main() -> Result<()> {
                                     "64 bit value that initializes PRNG, 0 - pick random seed"
                                 )
                                 .default_value("0")
+                        ).arg(
+                            Arg::new("noise")
+                                .short('n')
+                                .long("noise")
+                                .help(
+                                    "Distribution used by the blending pass: uniform, normal or exponential. The perturbation is signed and zero-centered so edges dither both ways"
+                                )
+                                .default_value("uniform")
                         )
                 ).subcommand_required(true),
         )
@@ -71,6 +166,7 @@ fn main() -> Result<()> {
         Some(("lua", params)) => {
             match params.subcommand() {
                 Some(("texture", params)) => texture::handle(params)?,
+                Some(("texture-synth", params)) => texture::handle_synth(params)?,
                 Some((&_, _)) | None => unreachable!(),
             }
         },
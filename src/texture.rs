@@ -7,6 +7,10 @@ enum TextureError {
     BlendingOutOfRange(u8),
     #[error("steps don't cover full range (0..255) or they overflow it")]
     PartialRange,
+    #[error("--gpu only supports uniform noise, not `{0}`; the kernel always applies its own index-hashed uniform offset")]
+    GpuNoiseUnsupported(Noise),
+    #[error("--gpu only supports single-candidate steps, not `{0}`; it always emits each step's first candidate")]
+    GpuMultiCandidateUnsupported(String),
 }
 
 /// Available error values returned by `Step::TryFrom`
@@ -16,21 +20,137 @@ enum StepError {
     ParseError(String),
 }
 
-/// Deserialized `$tile-name:$X..$Y` string.
+/// Available error values returned by `Noise::TryFrom`
+#[derive(Error, Debug)]
+enum NoiseError {
+    #[error("unknown noise distribution `{0}`, expected uniform|normal|exponential")]
+    ParseError(String),
+}
+
+/// Distribution used to perturb grayscale during the blending pass. Every
+/// variant produces a signed, zero-centered offset so edges dither both ways
+/// instead of drifting toward higher grayscale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Noise {
+    Uniform,
+    Normal,
+    Exponential,
+}
+
+impl Display for Noise {
+    /// Lowercase name, matching the `--noise` option spelling.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Noise::Uniform => "uniform",
+            Noise::Normal => "normal",
+            Noise::Exponential => "exponential",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for Noise {
+    type Error = NoiseError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "uniform" => Ok(Noise::Uniform),
+            "normal" => Ok(Noise::Normal),
+            "exponential" => Ok(Noise::Exponential),
+            _ => Err(NoiseError::ParseError(value.to_owned())),
+        }
+    }
+}
+
+/// Walker's alias table for O(1) weighted sampling of a step's candidate tiles.
+/// Built once per step; `sample` draws a uniform index plus a uniform fraction.
 #[derive(Debug)]
-struct Step<'a> {
+struct Alias {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl Alias {
+    /// Builds the table from raw `weights`. Weights are normalized so their mean
+    /// is 1, then the classic small/large worklists are drained to fill `prob`
+    /// and `alias`; any leftovers settle at `prob = 1`.
+    fn build(weights: &[f64]) -> Self {
+        let n = weights.len();
+        let sum: f64 = weights.iter().sum();
+        let mut scaled: Vec<f64> = weights.iter()
+            .map(|w| w * n as f64 / sum)
+            .collect();
+
+        let mut prob = vec![0.0f64; n];
+        let mut alias = vec![0usize; n];
+        let mut small: Vec<usize> = vec![];
+        let mut large: Vec<usize> = vec![];
+        for (i, &w) in scaled.iter().enumerate() {
+            if w < 1.0 { small.push(i) } else { large.push(i) }
+        }
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 { small.push(l) } else { large.push(l) }
+        }
+        // Whatever is left over (floating-point slack) is a sure thing.
+        for i in large.into_iter().chain(small) {
+            prob[i] = 1.0;
+        }
+
+        Self { prob, alias }
+    }
+
+    /// Draws a uniform index `i` and a uniform fraction `f`; returns `i` when
+    /// `f < prob[i]`, otherwise the aliased index.
+    fn sample(&self, rng: &mut ChaCha8Rng) -> usize {
+        let i = rng.gen_range(0..self.prob.len());
+        let f: f64 = rng.gen();
+        if f < self.prob[i] { i } else { self.alias[i] }
+    }
+}
+
+/// A single candidate tile of a step, with an optional relative weight.
+#[derive(Debug)]
+struct Candidate<'a> {
     name: &'a str,
+    weight: Option<f64>,
+}
+
+/// Deserialized `$tile-name:$X..$Y` string. A step may name several candidate
+/// tiles with optional `@weight`s, e.g. `grass-1@3,grass-2@1,grass-dry:0..80`;
+/// `Series::activate` then samples one candidate per cell through `alias`.
+#[derive(Debug)]
+struct Step<'a> {
+    candidates: Vec<Candidate<'a>>,
     range: RangeInclusive<u8>,
+    alias: Alias,
+    /// 1-based index of each candidate into the series-wide `as_lua_map`.
+    /// Populated by `Series::try_from` once the global tile list is known.
+    gidx: Vec<usize>,
 }
 
 impl<'a> Display for Step<'a> {
     /// Reproduces identical string that was provided during object construction.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}..{}", self.name, self.range.start(), self.range.end())
+        for (i, c) in self.candidates.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", c.name)?;
+            if let Some(w) = c.weight {
+                write!(f, "@{}", w)?;
+            }
+        }
+        write!(f, ":{}..{}", self.range.start(), self.range.end())
     }
 }
 
-/// Creates a `Step` from `$tile-name:$X..$Y` string.
+/// Creates a `Step` from `$tile-name:$X..$Y` string, where `$tile-name` may be
+/// a comma-separated list of `name[@weight]` candidates.
 impl<'a> TryFrom<&'a str> for Step<'a> {
     type Error = StepError;
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
@@ -52,9 +172,33 @@ impl<'a> TryFrom<&'a str> for Step<'a> {
             Err(_) => return Err(StepError::ParseError(value.to_owned())),
         };
 
+        let mut candidates = vec![];
+        for item in tile.split(',') {
+            let (name, weight) = match item.split('@').collect::<Vec<&str>>()[..] {
+                [name] => (name, None),
+                [name, w] => {
+                    let w = w.parse::<f64>()
+                        .map_err(|_| StepError::ParseError(value.to_owned()))?;
+                    (name, Some(w))
+                },
+                _ => return Err(StepError::ParseError(value.to_owned())),
+            };
+            if name.is_empty() {
+                return Err(StepError::ParseError(value.to_owned()));
+            }
+            candidates.push(Candidate { name, weight });
+        }
+
+        let weights: Vec<f64> = candidates.iter()
+            .map(|c| c.weight.unwrap_or(1.0))
+            .collect();
+        let alias = Alias::build(&weights);
+
         Ok(Self {
-            name: tile,
+            candidates,
             range: RangeInclusive::new(start, end),
+            alias,
+            gidx: vec![],
         })
     }
 }
@@ -64,14 +208,18 @@ impl<'a> TryFrom<&'a str> for Step<'a> {
 #[derive(Debug)]
 struct Series<'a> {
     series: Vec<Step<'a>>,
-    rng: SmallRng,
+    /// Distinct candidate tiles across all steps, in order of first appearance.
+    /// This is exactly what `as_lua_map` emits; steps index into it via `gidx`.
+    map: Vec<&'a str>,
+    rng: ChaCha8Rng,
     blending: RangeInclusive<u8>,
+    noise: Noise,
     seed: u64,
 }
 
 impl<'a> TryFrom<Vec<Step<'a>>> for Series<'a> {
     type Error = TextureError;
-    fn try_from(value: Vec<Step<'a>>) -> Result<Self, Self::Error> {
+    fn try_from(mut value: Vec<Step<'a>>) -> Result<Self, Self::Error> {
         // Check if all provided steps cumulatively exhaust u8 range.
         let range = value.iter()
             .fold(u8::MAX, |sum, v|
@@ -83,11 +231,33 @@ impl<'a> TryFrom<Vec<Step<'a>>> for Series<'a> {
             return Err(TextureError::PartialRange)
         }
 
-        let seed = thread_rng().gen_range(1..u64::MAX);
+        // Flatten every step's candidates into one deduplicated, 1-based map and
+        // remember, per candidate, where it lands in that map.
+        let mut map: Vec<&'a str> = vec![];
+        for step in value.iter_mut() {
+            let mut gidx = Vec::with_capacity(step.candidates.len());
+            for c in &step.candidates {
+                let index = match map.iter().position(|&n| n == c.name) {
+                    Some(i) => i,
+                    None => {
+                        map.push(c.name);
+                        map.len() - 1
+                    },
+                };
+                gidx.push(index + 1);
+            }
+            step.gidx = gidx;
+        }
+
+        // `OsRng` gives a clean split: a zero seed means "pick for me",
+        // any explicit seed reproduces the map byte-for-byte anywhere.
+        let seed = OsRng.gen_range(1..u64::MAX);
         Ok(Self {
             series: value,
-            rng: SmallRng::seed_from_u64(seed),
+            map,
+            rng: ChaCha8Rng::seed_from_u64(seed),
             blending: RangeInclusive::new(0, 0),
+            noise: Noise::Uniform,
             seed
         })
     }
@@ -111,24 +281,79 @@ impl<'a> Series<'a> {
     /// Once the reference to Step is found, return it's index/activation value.
     /// NOTE: The hard assumption is that `Vec<Step>` exhausts entire `u8` width.
     fn activate(&mut self, mut value: u8) -> u8 {
-        if !self.blending.is_empty() {
-            // If it overflows clamp it at u8::MAX
-            value = value.saturating_add(self.rng.gen_range(self.blending.clone()));
+        // `RangeInclusive::new(0, end)` is never empty, so gate on the actual
+        // factor: with blending disabled no perturbation is drawn (candidate
+        // sampling below still draws from the RNG stream for every cell).
+        if *self.blending.end() > 0 {
+            // Signed, zero-centered offset. A saturating signed add keeps the
+            // result inside [0, 255] while letting edges dither both ways.
+            let delta = self.perturbation();
+            value = (i32::from(value) + delta).clamp(0, i32::from(u8::MAX)) as u8;
         }
 
         let (index, _) = self.series.iter()
             .enumerate()
             .find(|&(_, v)| v.range.contains(&value))
             .unwrap();
-        // Lua is 1-based.
-        (index + 1) as u8
+        // Sample one candidate tile for this cell and map it back onto the
+        // series-wide 1-based tile index.
+        let candidate = self.series[index].alias.sample(&mut self.rng);
+        self.series[index].gidx[candidate] as u8
+    }
+
+    /// Draws a single signed perturbation according to the configured `Noise`
+    /// distribution. The magnitude is anchored on the `[0..255]`-mapped blending
+    /// factor (the inclusive end of `self.blending`).
+    fn perturbation(&mut self) -> i32 {
+        let factor = i32::from(*self.blending.end());
+        match self.noise {
+            // Symmetric uniform over [-factor, factor].
+            Noise::Uniform => self.rng.gen_range(-factor..=factor),
+            // Box-Muller, scaled by a std-dev derived from the factor.
+            Noise::Normal => {
+                let sigma = f32::from(*self.blending.end()) / f32::from(u8::MAX) * 64.0;
+                // u1 must be in (0, 1] for the logarithm.
+                let u1: f32 = loop {
+                    let u = self.rng.gen::<f32>();
+                    if u > 0.0 {
+                        break u;
+                    }
+                };
+                let u2: f32 = self.rng.gen::<f32>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos();
+                (z * sigma).round() as i32
+            },
+            // One-sided exponential made symmetric with a random sign.
+            Noise::Exponential => {
+                let scale = f32::from(*self.blending.end()) / f32::from(u8::MAX) * 64.0;
+                let u: f32 = loop {
+                    let u = self.rng.gen::<f32>();
+                    if u > 0.0 {
+                        break u;
+                    }
+                };
+                let magnitude = -(u.ln()) * scale;
+                let sign = if self.rng.gen::<bool>() { 1.0 } else { -1.0 };
+                (magnitude * sign).round() as i32
+            },
+        }
+    }
+
+    /// Selects the distribution used by the blending pass.
+    fn with_noise(mut self, noise: Noise) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    fn noise(&self) -> Noise {
+        self.noise
     }
 
     /// Applies blending onto values passed to `Self::activate`
     fn with_blending(mut self, seed: u64, factor: u8) -> Self {
         // If seed == 0, then we already have default instance in Self::from.
         if seed != 0 {
-            self.rng = SmallRng::seed_from_u64(seed);
+            self.rng = ChaCha8Rng::seed_from_u64(seed);
             self.seed = seed;
         }
 
@@ -159,9 +384,9 @@ impl<'a> Series<'a> {
     /// Dump a lua array representating a map of activation values and tile names.
     fn as_lua_map(&self) -> String {
         let mut s = String::from("{");
-        for (index, step) in self.series.iter().enumerate() {
+        for (index, name) in self.map.iter().enumerate() {
             s.push_str(
-                &format!("[{}]=\"{}\",", (index + 1), step.name)
+                &format!("[{}]=\"{}\",", (index + 1), name)
             )
         }
         s.push('}');
@@ -192,8 +417,14 @@ fn load_grayscale<P: AsRef<Path>>(path: P) -> Result<Grid<u8>> {
     Ok(grid)
 }
 
-/// Generates a comment with additional information.
-fn header_lua(series: &Series) -> String {
+/// Generates a comment with additional information. When a `preset` is supplied
+/// it is appended as a re-runnable block: a lua long-comment wrapping a pure
+/// TOML body (the `bb --preset` marker lives on its own `#`-commented TOML
+/// line), so a user can lift the text between `--[==[` and `]==]` into a file
+/// and feed it straight back in with `--preset` (no per-line stripping needed).
+/// The level-2 brackets keep the TOML's own `[[pass]]` headers from closing
+/// the comment early.
+fn header_lua(series: &Series, preset: Option<&Preset>) -> String {
     let mut s = String::from("-- bb");
     s.push_str(
         &format!(" --seed {}", series.seed().to_string())
@@ -201,10 +432,21 @@ fn header_lua(series: &Series) -> String {
     s.push_str(
         &format!(" --blending {}", series.blending())
     );
+    s.push_str(
+        &format!(" --noise {}", series.noise())
+    );
     s.push_str(
         &format!(" --steps {}", series.to_string())
     );
 
+    if let Some(preset) = preset {
+        if let Ok(toml) = toml::to_string(preset) {
+            s.push_str("\n--[==[\n# bb --preset\n");
+            s.push_str(&toml);
+            s.push_str("]==]");
+        }
+    }
+
     s
 }
 
@@ -238,7 +480,7 @@ fn to_lua(grid: &Grid<u8>, series: &Series) -> String {
     let height = grid[0].len();
 
     let mut code = String::from(
-        &format!("{}\n", header_lua(series))
+        &format!("{}\n", header_lua(series, None))
     );
     code.push_str("local mod={};");
     code.push_str(
@@ -264,6 +506,212 @@ fn to_lua(grid: &Grid<u8>, series: &Series) -> String {
     code
 }
 
+/// Side of a single square quilting patch, in pixels.
+const QUILT_BLOCK: usize = 32;
+/// Overlap between neighbouring patches. Roughly a sixth of the block side.
+const QUILT_OVERLAP: usize = QUILT_BLOCK / 6;
+/// A candidate block is kept for random selection if its overlap error is
+/// within `(1 + QUILT_EPSILON)` of the best observed error.
+const QUILT_EPSILON: f64 = 0.1;
+
+/// Effective dimensions of a (possibly border-clamped) quilting patch, `(width,
+/// height)`.
+type PatchSize = (usize, usize);
+
+/// Sum-of-squared-differences between the overlap of a source block anchored at
+/// `src_at` (`(sy, sx)`) in `sample` and the already-filled region of `out` at
+/// `out_at` (`(py, px)`). `size` holds the effective patch dimensions (clamped
+/// at the output border). Only the left (`px > 0`) and top (`py > 0`) strips
+/// contribute.
+fn overlap_error(
+    out: &Grid<u8>,
+    sample: &Grid<u8>,
+    out_at: (usize, usize),
+    src_at: (usize, usize),
+    size: PatchSize,
+) -> u64 {
+    let (py, px) = out_at;
+    let (sy, sx) = src_at;
+    let (bw, bh) = size;
+    // A remainder patch at the right/bottom border may be narrower than the
+    // overlap itself, so clamp the strips to the patch dimensions.
+    let ow = QUILT_OVERLAP.min(bw);
+    let oh = QUILT_OVERLAP.min(bh);
+    let mut err = 0u64;
+    if px > 0 {
+        for dy in 0..bh {
+            for dx in 0..ow {
+                let a = i64::from(out[py + dy][px + dx]);
+                let b = i64::from(sample[sy + dy][sx + dx]);
+                err += (a - b).pow(2) as u64;
+            }
+        }
+    }
+    if py > 0 {
+        for dy in 0..oh {
+            for dx in 0..bw {
+                let a = i64::from(out[py + dy][px + dx]);
+                let b = i64::from(sample[sy + dy][sx + dx]);
+                err += (a - b).pow(2) as u64;
+            }
+        }
+    }
+    err
+}
+
+/// Minimum-error boundary cut over a rectangular error surface `e` laid out as
+/// `rows x cols`. Builds the cumulative matrix
+/// `E[i][j] = e[i][j] + min(E[i-1][j-1], E[i-1][j], E[i-1][j+1])` row by row,
+/// then backtracks from the minimum of the last row to produce, for every row,
+/// the column at which the seam sits.
+fn min_error_seam(e: &[Vec<u64>]) -> Vec<usize> {
+    let rows = e.len();
+    let cols = e[0].len();
+    let mut acc = e.to_vec();
+    for i in 1..rows {
+        for j in 0..cols {
+            let mut best = acc[i - 1][j];
+            if j > 0 {
+                best = best.min(acc[i - 1][j - 1]);
+            }
+            if j + 1 < cols {
+                best = best.min(acc[i - 1][j + 1]);
+            }
+            acc[i][j] += best;
+        }
+    }
+
+    let mut seam = vec![0usize; rows];
+    // Seed the backtrack with the cheapest column of the bottom row.
+    let (mut j, _) = acc[rows - 1].iter().enumerate()
+        .min_by_key(|&(_, v)| *v)
+        .unwrap();
+    seam[rows - 1] = j;
+    for i in (0..rows - 1).rev() {
+        let mut best = acc[i][j];
+        let mut best_j = j;
+        if j > 0 && acc[i][j - 1] < best {
+            best = acc[i][j - 1];
+            best_j = j - 1;
+        }
+        if j + 1 < cols && acc[i][j + 1] < best {
+            best_j = j + 1;
+        }
+        j = best_j;
+        seam[i] = j;
+    }
+
+    seam
+}
+
+/// Grows a seamless grayscale field of `width x height` pixels out of a small
+/// `sample` using Efros-Freeman image quilting. The output is tiled with
+/// overlapping `QUILT_BLOCK` patches; each patch is chosen from the source by
+/// sum-of-squared-differences over the already-filled overlap and stitched in
+/// along a minimum-error boundary cut so seams disappear.
+fn quilt(sample: &Grid<u8>, width: usize, height: usize, rng: &mut ChaCha8Rng) -> Grid<u8> {
+    let sh = sample.len();
+    let sw = sample[0].len();
+    // Clamp the block to the sample so tiny swatches still work.
+    let block = QUILT_BLOCK.min(sh).min(sw);
+    let step = block.saturating_sub(QUILT_OVERLAP).max(1);
+
+    let mut out = vec![vec![0u8; width]; height];
+
+    let mut py = 0;
+    while py < height {
+        let mut px = 0;
+        while px < width {
+            let bh = block.min(height - py);
+            let bw = block.min(width - px);
+            // A remainder patch may be narrower than the overlap, so clamp the
+            // overlap strips to the patch dimensions to stay in bounds.
+            let ow = QUILT_OVERLAP.min(bw);
+            let oh = QUILT_OVERLAP.min(bh);
+            // Candidate search windows are clamped at the source borders.
+            let last_sy = sh - bh;
+            let last_sx = sw - bw;
+
+            let (sy, sx) = if py == 0 && px == 0 {
+                // Top-left corner has no overlap, so seed it randomly.
+                (rng.gen_range(0..=last_sy), rng.gen_range(0..=last_sx))
+            } else {
+                let mut best = u64::MAX;
+                let mut candidates: Vec<(u64, usize, usize)> = vec![];
+                for cy in 0..=last_sy {
+                    for cx in 0..=last_sx {
+                        let err = overlap_error(&out, sample, (py, px), (cy, cx), (bw, bh));
+                        best = best.min(err);
+                        candidates.push((err, cy, cx));
+                    }
+                }
+                // Keep everything within (1 + epsilon) of the best error.
+                let threshold = (best as f64 * (1.0 + QUILT_EPSILON)) as u64;
+                candidates.retain(|&(err, _, _)| err <= threshold);
+                let pick = rng.gen_range(0..candidates.len());
+                let (_, cy, cx) = candidates[pick];
+                (cy, cx)
+            };
+
+            // Vertical seam over the left overlap strip.
+            let vseam = if px > 0 {
+                let mut e = vec![vec![0u64; ow]; bh];
+                for dy in 0..bh {
+                    for dx in 0..ow {
+                        let a = i64::from(out[py + dy][px + dx]);
+                        let b = i64::from(sample[sy + dy][sx + dx]);
+                        e[dy][dx] = (a - b).pow(2) as u64;
+                    }
+                }
+                Some(min_error_seam(&e))
+            } else {
+                None
+            };
+
+            // Horizontal seam over the top overlap strip. The error surface is
+            // transposed so the same vertical routine produces a row per column.
+            let hseam = if py > 0 {
+                let mut e = vec![vec![0u64; oh]; bw];
+                for dx in 0..bw {
+                    for dy in 0..oh {
+                        let a = i64::from(out[py + dy][px + dx]);
+                        let b = i64::from(sample[sy + dy][sx + dx]);
+                        e[dx][dy] = (a - b).pow(2) as u64;
+                    }
+                }
+                Some(min_error_seam(&e))
+            } else {
+                None
+            };
+
+            // Copy the new patch, but only on the new-patch side of each seam.
+            for dy in 0..bh {
+                for dx in 0..bw {
+                    let mut take_new = true;
+                    if let Some(seam) = &vseam {
+                        if dx < ow && dx < seam[dy] {
+                            take_new = false;
+                        }
+                    }
+                    if let Some(seam) = &hseam {
+                        if dy < oh && dy < seam[dx] {
+                            take_new = false;
+                        }
+                    }
+                    if take_new {
+                        out[py + dy][px + dx] = sample[sy + dy][sx + dx];
+                    }
+                }
+            }
+
+            px += step;
+        }
+        py += step;
+    }
+
+    out
+}
+
 /// Use supplied arguments to generate representation of a image texture as a lua array.
 /// This function requires `step` and `image` input parameters.
 /// Each `step` must adhere to the following format: `$tile-name:$X..$Y`
@@ -277,6 +725,11 @@ fn to_lua(grid: &Grid<u8>, series: &Series) -> String {
 /// This will instruct the function to place tiles at those specific thresholds of a
 /// grayscale derived from the image.
 pub fn handle(args: &ArgMatches) -> Result<()> {
+    // A preset fully describes the job on its own, so it short-circuits the
+    // per-flag path below.
+    if let Some(path) = args.get_one::<String>("preset") {
+        return handle_preset(path);
+    }
     // Iterate over each step string and convert into Step struct.
     let steps = args.get_many::<String>("steps").unwrap()
         .map(|v| v.as_str().try_into())
@@ -290,18 +743,49 @@ pub fn handle(args: &ArgMatches) -> Result<()> {
     // Then convert into Series.
     let seed = args.get_one::<String>("seed").unwrap()
         .parse::<u64>()?;
+    let noise = args.get_one::<String>("noise").unwrap()
+        .as_str().try_into()?;
     let mut series = Series::try_from(steps)?
-        .with_blending(seed, blend);
+        .with_blending(seed, blend)
+        .with_noise(noise);
     // Load and convert image into grayscale, iterate over each pixel and convert
     // it into activation value. The activation value here means just an index of
     // a Step held in Series.
     let path = args.get_one::<String>("image").unwrap();
-    let grid: Grid<u8> = load_grayscale(path)?.
-        into_iter().map(
+    let src = load_grayscale(path)?;
+    // The GPU path is an optional, feature-gated accelerator; the CPU nested
+    // `map` remains the default and the fallback when the feature is absent.
+    #[cfg(feature = "gpu")]
+    let use_gpu = args.get_flag("gpu");
+    #[cfg(not(feature = "gpu"))]
+    let use_gpu = false;
+
+    // The kernel always applies a uniform index-hashed offset and always
+    // emits a step's first candidate, so flags promising otherwise would be
+    // silently ignored; refuse instead of diverging from what was asked for.
+    if use_gpu {
+        if series.noise() != Noise::Uniform {
+            bail!(TextureError::GpuNoiseUnsupported(series.noise()))
+        }
+        if let Some(step) = series.series.iter().find(|s| s.candidates.len() > 1) {
+            bail!(TextureError::GpuMultiCandidateUnsupported(step.to_string()))
+        }
+    }
+
+    let grid: Grid<u8> = if use_gpu {
+        #[cfg(feature = "gpu")]
+        {
+            gpu::run(&src, &series)?
+        }
+        #[cfg(not(feature = "gpu"))]
+        unreachable!()
+    } else {
+        src.into_iter().map(
             |v| v.into_iter().map(
                 |v| series.activate(v)
             ).collect()
-        ).collect();
+        ).collect()
+    };
     // Now combine information from grid and series to compute final lua output.
     let code = to_lua(&grid, &series);
 
@@ -310,3 +794,554 @@ pub fn handle(args: &ArgMatches) -> Result<()> {
 
     Ok(())
 }
+
+/// A single generation pass within a preset. Mirrors the CLI flags of `texture`
+/// (plus optional `width`/`height`, which switch the pass over to the image
+/// quilting path) so a preset can describe exactly what a command line would.
+#[derive(Debug, Deserialize, Serialize)]
+struct Pass {
+    name: String,
+    image: String,
+    steps: Vec<String>,
+    #[serde(default)]
+    blending: u8,
+    #[serde(default = "default_noise")]
+    noise: String,
+    #[serde(default)]
+    seed: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    width: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    height: Option<usize>,
+}
+
+/// Declarative description of a full generation job: a shared output path plus
+/// one or more named passes that are composed into a single lua file.
+#[derive(Debug, Deserialize, Serialize)]
+struct Preset {
+    output: String,
+    #[serde(rename = "pass")]
+    passes: Vec<Pass>,
+}
+
+/// Default `--noise` distribution when a pass omits it.
+fn default_noise() -> String {
+    Noise::Uniform.to_string()
+}
+
+/// Emits a `mod.grid` body (`{{..},{..},}`) for a single pass grid.
+fn grid_lua(grid: &Grid<u8>) -> String {
+    let mut s = String::from("{");
+    for row in grid {
+        s.push('{');
+        for tile_id in row {
+            s.push_str(&tile_id.to_string());
+            s.push(',')
+        }
+        s.push_str("},");
+    }
+    s.push('}');
+    s
+}
+
+/// Runs a preset: each pass is parsed into the very same `Series`/`Step` types
+/// the CLI uses, activated over its image (directly, or grown via quilting when
+/// `width`/`height` are given), and the results are composed into one lua file.
+/// The file is prefixed with a re-runnable preset block so generated maps stay
+/// self-describing.
+fn handle_preset<P: AsRef<Path>>(path: P) -> Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let preset: Preset = toml::from_str(&contents)?;
+
+    // Render each pass into its `mod.passes[name]` entry up front; the borrow of
+    // `pass.steps` by `Series` ends before the next iteration. The first pass's
+    // `Series` is kept so the header summary can be emitted through `header_lua`.
+    let mut rendered: Vec<String> = vec![];
+    let mut first_series: Option<Series> = None;
+    for (pi, pass) in preset.passes.iter().enumerate() {
+        if pass.blending > 100 {
+            bail!(TextureError::BlendingOutOfRange(pass.blending))
+        }
+        let steps = pass.steps.iter()
+            .map(|v| v.as_str().try_into())
+            .collect::<Result<Vec<Step>, _>>()?;
+        let noise: Noise = pass.noise.as_str().try_into()?;
+        let mut series = Series::try_from(steps)?
+            .with_blending(pass.seed, pass.blending)
+            .with_noise(noise);
+
+        let sample = load_grayscale(&pass.image)?;
+        // A pass with explicit dimensions is grown from its sample; otherwise
+        // the image is thresholded 1:1 just like `texture`.
+        let field = match (pass.width, pass.height) {
+            (Some(w), Some(h)) => quilt(&sample, w, h, &mut series.rng),
+            _ => sample,
+        };
+        let grid: Grid<u8> = field.into_iter().map(
+            |v| v.into_iter().map(|v| series.activate(v)).collect()
+        ).collect();
+
+        let width = grid.len();
+        let height = grid[0].len();
+        rendered.push(format!(
+            "mod.passes[\"{}\"]={{width={};height={};map={};grid={};}};",
+            pass.name, width, height, series.as_lua_map(), grid_lua(&grid)
+        ));
+        if pi == 0 {
+            first_series = Some(series);
+        }
+    }
+
+    // The header carries a re-runnable preset block, emitted through `header_lua`
+    // so every generated map is self-describing the same way the single-pass
+    // output is.
+    let mut code = match &first_series {
+        Some(series) => format!("{}\n", header_lua(series, Some(&preset))),
+        None => String::new(),
+    };
+    code.push_str("local mod={};mod.passes={};");
+    for entry in &rendered {
+        code.push_str(entry);
+    }
+    code.push_str("return mod");
+
+    File::create(&preset.output)?.write_all(code.as_bytes())?;
+
+    Ok(())
+}
+
+/// Like `handle`, but the reference image is treated as a small sample that is
+/// grown to the requested `--width`/`--height` with image quilting before being
+/// thresholded. This lets a tiny swatch (e.g. 64x64) seed an arbitrarily large,
+/// seamless map. The `Step`/`Series`/`to_lua` pipeline is shared verbatim with
+/// `handle`; only the grid handed to it is synthesized instead of loaded 1:1.
+pub fn handle_synth(args: &ArgMatches) -> Result<()> {
+    let steps = args.get_many::<String>("steps").unwrap()
+        .map(|v| v.as_str().try_into())
+        .collect::<Result<Vec<Step>, _>>()?;
+    let blend = args.get_one::<String>("blending").unwrap()
+        .parse::<u8>()?;
+    if blend > 100 {
+        bail!(TextureError::BlendingOutOfRange(blend))
+    }
+    let seed = args.get_one::<String>("seed").unwrap()
+        .parse::<u64>()?;
+    let noise = args.get_one::<String>("noise").unwrap()
+        .as_str().try_into()?;
+    let mut series = Series::try_from(steps)?
+        .with_blending(seed, blend)
+        .with_noise(noise);
+    // Target dimensions of the synthesized field.
+    let width = args.get_one::<String>("width").unwrap()
+        .parse::<usize>()?;
+    let height = args.get_one::<String>("height").unwrap()
+        .parse::<usize>()?;
+    // Grow the sample into a large seamless field, then thread it through the
+    // very same per-pixel activation the direct `texture` path uses.
+    let path = args.get_one::<String>("image").unwrap();
+    let sample = load_grayscale(path)?;
+    let grid: Grid<u8> = quilt(&sample, width, height, &mut series.rng)
+        .into_iter().map(
+            |v| v.into_iter().map(
+                |v| series.activate(v)
+            ).collect()
+        ).collect();
+    let code = to_lua(&grid, &series);
+
+    let path = args.get_one::<String>("output").unwrap();
+    File::create(path)?.write_all(code.as_bytes())?;
+
+    Ok(())
+}
+
+/// Optional wgpu-backed activation path. Enabled with the `gpu` feature.
+///
+/// Per texel the kernel perturbs the grayscale with a signed uniform offset
+/// seeded from the global seed plus the texel's linear index (so the result is
+/// deterministic and independent of evaluation order), locates the containing
+/// step with a `start..=end` test over the boundary arrays, and writes the
+/// step's 1-based map index back.
+///
+/// NOTE: this is *not* bit-compatible with the default `Series::activate`. That
+/// path draws its blend offset sequentially from `ChaCha8Rng` and supports
+/// per-cell weighted sampling and the `normal`/`exponential` distributions; the
+/// GPU path uses an index-hashed uniform offset and each step's first candidate.
+/// The determinism contract the kernel upholds is GPU == [`cpu_reference`], its
+/// pure-CPU twin — that is the pair asserted to match in tests.
+#[cfg(feature = "gpu")]
+mod gpu {
+    use super::*;
+    use wgpu::util::DeviceExt;
+
+    /// Lowbias32 integer hash. Replicated verbatim in the WGSL kernel so the CPU
+    /// reference and the GPU output agree bit-for-bit.
+    fn hash(mut x: u32) -> u32 {
+        x ^= x >> 16;
+        x = x.wrapping_mul(0x7feb_352d);
+        x ^= x >> 15;
+        x = x.wrapping_mul(0x846c_a68b);
+        x ^= x >> 16;
+        x
+    }
+
+    /// Activates a single texel the same way the WGSL kernel does. Step lookup
+    /// is an order-independent `start..=end` containment test, matching the CPU
+    /// `Series::activate` semantics rather than assuming sorted boundaries.
+    fn activate_texel(value: u8, index: u32, starts: &[u32], ends: &[u32], remap: &[u32], seed_mix: u32, factor: u32) -> u8 {
+        let mut v = i32::from(value);
+        if factor > 0 {
+            let n = hash(index ^ hash(seed_mix));
+            let span = 2 * factor + 1;
+            let delta = (n % span) as i32 - factor as i32;
+            v = v.clamp(0, 255) + delta;
+            v = v.clamp(0, 255);
+        }
+        let v = v as u32;
+        for i in 0..ends.len() {
+            if v >= starts[i] && v <= ends[i] {
+                return remap[i] as u8;
+            }
+        }
+        remap[ends.len() - 1] as u8
+    }
+
+    /// Pure-CPU twin of the compute kernel. Used as the fallback when no adapter
+    /// is available and as the bit-for-bit reference the GPU path is checked
+    /// against.
+    fn cpu_reference(grid: &Grid<u8>, starts: &[u32], ends: &[u32], remap: &[u32], seed: u64, factor: u32) -> Grid<u8> {
+        let seed_mix = (seed as u32) ^ ((seed >> 32) as u32);
+        grid.iter().enumerate().map(|(y, row)| {
+            let width = row.len();
+            row.iter().enumerate().map(|(x, &v)| {
+                let index = (y * width + x) as u32;
+                activate_texel(v, index, starts, ends, remap, seed_mix, factor)
+            }).collect()
+        }).collect()
+    }
+
+    const SHADER: &str = r#"
+struct Params {
+    seed_mix: u32,
+    factor: u32,
+    count: u32,
+    steps: u32,
+};
+
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+@group(0) @binding(1) var<storage, read> ends: array<u32>;
+@group(0) @binding(2) var<storage, read> remap: array<u32>;
+@group(0) @binding(3) var<uniform> params: Params;
+@group(0) @binding(4) var<storage, read_write> output: array<u32>;
+@group(0) @binding(5) var<storage, read> starts: array<u32>;
+
+fn hash(x0: u32) -> u32 {
+    var x = x0;
+    x = x ^ (x >> 16u);
+    x = x * 0x7feb352du;
+    x = x ^ (x >> 15u);
+    x = x * 0x846ca68bu;
+    x = x ^ (x >> 16u);
+    return x;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let i = gid.x;
+    if (i >= params.count) {
+        return;
+    }
+    var v = i32(input[i]);
+    if (params.factor > 0u) {
+        let n = hash(i ^ hash(params.seed_mix));
+        let span = 2u * params.factor + 1u;
+        let delta = i32(n % span) - i32(params.factor);
+        v = clamp(v, 0, 255) + delta;
+        v = clamp(v, 0, 255);
+    }
+    let uv = u32(v);
+    var result = remap[params.steps - 1u];
+    for (var s: u32 = 0u; s < params.steps; s = s + 1u) {
+        if (uv >= starts[s] && uv <= ends[s]) {
+            result = remap[s];
+            break;
+        }
+    }
+    output[i] = result;
+}
+"#;
+
+    /// Runs the activation pass for `series` over `grid` on the GPU, falling back
+    /// to the CPU twin when no adapter can be acquired.
+    pub fn run(grid: &Grid<u8>, series: &Series) -> Result<Grid<u8>> {
+        let starts: Vec<u32> = series.series.iter()
+            .map(|s| u32::from(*s.range.start()))
+            .collect();
+        let ends: Vec<u32> = series.series.iter()
+            .map(|s| u32::from(*s.range.end()))
+            .collect();
+        let remap: Vec<u32> = series.series.iter()
+            .map(|s| s.gidx[0] as u32)
+            .collect();
+        let factor = u32::from(*series.blending.end());
+        let seed = series.seed();
+
+        match dispatch(grid, &starts, &ends, &remap, seed, factor) {
+            Some(out) => Ok(out),
+            // No usable adapter: honour the "CPU is the fallback" contract.
+            None => Ok(cpu_reference(grid, &starts, &ends, &remap, seed, factor)),
+        }
+    }
+
+    /// Performs the actual wgpu upload/dispatch/readback. Returns `None` when no
+    /// adapter is available so the caller can fall back to the CPU twin.
+    fn dispatch(grid: &Grid<u8>, starts: &[u32], ends: &[u32], remap: &[u32], seed: u64, factor: u32) -> Option<Grid<u8>> {
+        let height = grid.len();
+        let width = grid[0].len();
+        let count = width * height;
+        let flat: Vec<u32> = grid.iter().flatten().map(|&v| u32::from(v)).collect();
+
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(
+            instance.request_adapter(&wgpu::RequestAdapterOptions::default())
+        )?;
+        let (device, queue) = pollster::block_on(
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None)
+        ).ok()?;
+
+        let input = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("input"),
+            contents: bytemuck::cast_slice(&flat),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let ends_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("ends"),
+            contents: bytemuck::cast_slice(ends),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let starts_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("starts"),
+            contents: bytemuck::cast_slice(starts),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let remap_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("remap"),
+            contents: bytemuck::cast_slice(remap),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let params = [
+            (seed as u32) ^ ((seed >> 32) as u32),
+            factor,
+            count as u32,
+            ends.len() as u32,
+        ];
+        let params_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: bytemuck::cast_slice(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let size = (count * std::mem::size_of::<u32>()) as u64;
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("output"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("activate"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("activate"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("activate"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: input.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: ends_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: remap_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: params_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 4, resource: output.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 5, resource: starts_buf.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let groups = (count as u32).div_ceil(64);
+            pass.dispatch_workgroups(groups, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, size);
+        queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        let result: Vec<u32> = bytemuck::cast_slice(&data).to_vec();
+        drop(data);
+        readback.unmap();
+
+        // Re-assemble the flat result into the row-major `Grid<u8>`.
+        let mut out = vec![vec![0u8; width]; height];
+        for (i, &value) in result.iter().enumerate() {
+            out[i / width][i % width] = value as u8;
+        }
+        Some(out)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// For a fixed seed the GPU path must match its pure-CPU twin
+        /// (`cpu_reference`) bit-for-bit. This is the only pair that can agree:
+        /// the kernel deliberately uses an index-hashed uniform offset, not the
+        /// sequential `ChaCha8Rng` stream of `Series::activate`.
+        ///
+        /// Calls `dispatch` directly (not `run`) so the comparison exercises the
+        /// actual wgpu path. `run`'s fallback would let this assert the CPU twin
+        /// against itself on any machine without a usable adapter, which is the
+        /// common case in headless CI; skip instead of faking coverage there.
+        #[test]
+        fn gpu_matches_cpu_reference() {
+            let steps = vec![
+                Step::try_from("water:0..127").unwrap(),
+                Step::try_from("grass:128..255").unwrap(),
+            ];
+            let series = Series::try_from(steps).unwrap().with_blending(42, 30);
+            let grid: Grid<u8> = vec![
+                vec![0, 40, 127, 200, 255],
+                vec![128, 90, 10, 130, 60],
+            ];
+
+            let starts: Vec<u32> = series.series.iter()
+                .map(|s| u32::from(*s.range.start())).collect();
+            let ends: Vec<u32> = series.series.iter()
+                .map(|s| u32::from(*s.range.end())).collect();
+            let remap: Vec<u32> = series.series.iter()
+                .map(|s| s.gidx[0] as u32).collect();
+            let factor = u32::from(*series.blending.end());
+            let seed = series.seed();
+
+            let Some(gpu_out) = dispatch(&grid, &starts, &ends, &remap, seed, factor) else {
+                eprintln!("skipping gpu_matches_cpu_reference: no usable wgpu adapter");
+                return;
+            };
+            let reference = cpu_reference(&grid, &starts, &ends, &remap, seed, factor);
+            assert_eq!(gpu_out, reference);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stable FNV-1a (64-bit) so a pinned seed can be tied to a known output
+    /// hash without depending on the standard library's unspecified hasher.
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        for &b in bytes {
+            h ^= u64::from(b);
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        h
+    }
+
+    fn series(seed: u64, blending: u8) -> Series<'static> {
+        let steps = vec![
+            Step::try_from("water:0..127").unwrap(),
+            Step::try_from("grass:128..255").unwrap(),
+        ];
+        Series::try_from(steps).unwrap().with_blending(seed, blending)
+    }
+
+    /// Pinning the seed must yield byte-identical lua on every platform and
+    /// build. The hash is computed over a fixed grid with blending disabled, so
+    /// the bytes depend only on the portable code path (the seed still rides
+    /// along in the header comment).
+    #[test]
+    fn pinned_seed_hashes_to_known_value() {
+        let mut series = series(12345, 0);
+        let input: Grid<u8> = vec![
+            vec![0, 64, 127, 200],
+            vec![128, 255, 10, 130],
+            vec![60, 127, 128, 90],
+        ];
+        let grid: Grid<u8> = input.into_iter().map(
+            |row| row.into_iter().map(|v| series.activate(v)).collect()
+        ).collect();
+        let code = to_lua(&grid, &series);
+
+        assert_eq!(fnv1a(code.as_bytes()), 0xa82d_85c4_5541_d257);
+    }
+
+    /// A pinned seed with blending enabled must reproduce exactly across runs.
+    #[test]
+    fn pinned_seed_is_reproducible() {
+        let render = || {
+            let mut series = series(777, 40);
+            let input: Grid<u8> = vec![vec![10, 90, 140, 220], vec![200, 30, 127, 128]];
+            let grid: Grid<u8> = input.into_iter().map(
+                |row| row.into_iter().map(|v| series.activate(v)).collect()
+            ).collect();
+            to_lua(&grid, &series)
+        };
+
+        assert_eq!(render(), render());
+    }
+
+    /// `min_error_seam` must hug the cheap side of a known error surface: a
+    /// vertical strip where column 0 is uniformly cheap and column 1 uniformly
+    /// expensive should backtrack to column 0 on every row.
+    #[test]
+    fn seam_follows_known_low_error_side() {
+        let e = vec![
+            vec![1u64, 9],
+            vec![1, 9],
+            vec![1, 9],
+        ];
+        assert_eq!(min_error_seam(&e), vec![0, 0, 0]);
+    }
+
+    /// When the cheap column shifts partway down, the seam must follow it
+    /// rather than sticking to the first row's minimum.
+    #[test]
+    fn seam_tracks_a_diagonal_cheap_path() {
+        let e = vec![
+            vec![1u64, 9, 9],
+            vec![9, 1, 9],
+            vec![9, 9, 1],
+        ];
+        assert_eq!(min_error_seam(&e), vec![0, 1, 2]);
+    }
+
+    /// Walker's alias sampling must converge to the configured weights: with
+    /// `a@3,b@1` roughly 3 of every 4 draws should land on `a` over a large,
+    /// seeded sample.
+    #[test]
+    fn alias_sampling_converges_to_weights() {
+        let step = Step::try_from("a@3,b@1:0..255").unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(99);
+        let draws = 20_000;
+        let a_count = (0..draws).filter(|_| step.alias.sample(&mut rng) == 0).count();
+        let ratio = a_count as f64 / draws as f64;
+
+        assert!((ratio - 0.75).abs() < 0.02, "ratio was {ratio}");
+    }
+}